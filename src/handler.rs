@@ -4,10 +4,12 @@ use tokio::net::TcpStream;
 
 use crate::protocol::schema::requests::apiversions::ApiVersionRequest;
 use crate::protocol::schema::requests::describetopic::DescribeTopicPartitions;
+use crate::protocol::schema::requests::metadata::MetadataRequest;
 use crate::protocol::schema::Respond;
 use crate::protocol::RequestBase;
 
 pub enum Request {
+    Metadata,
     ApiVersions,
     DescribeTopicsPartitions,
     Unknown,
@@ -15,6 +17,7 @@ pub enum Request {
 
 fn get_request(key: i16) -> Request {
     match key {
+        3 => Request::Metadata,
         18 => Request::ApiVersions,
         75 => Request::DescribeTopicsPartitions,
         _ => Request::Unknown,
@@ -29,17 +32,61 @@ async fn respond(socket: &mut TcpStream, buf: &[u8]) {
     let _ = socket.flush().await;
 }
 
+/// Closes our side of the connection so the client isn't left waiting for a response that
+/// will never come.
+async fn close(socket: &mut TcpStream) {
+    if let Err(e) = socket.shutdown().await {
+        eprintln!("failed to shutdown socket; err = {e:?}");
+    }
+}
+
 pub async fn dispatch_request(req: RequestBase, buf: &mut BytesMut, socket: &mut TcpStream) {
     let api_key = get_request(req.api_key);
 
     let past_base = req.base_size as usize;
 
     match api_key {
+        Request::Metadata => {
+            let advertised_address = match socket.local_addr() {
+                Ok(addr) => addr,
+                Err(e) => {
+                    eprintln!("Error while reading the local address: {e:?}");
+                    return;
+                }
+            };
+            let Some(body) = buf.get(past_base + 1..) else {
+                eprintln!("Metadata request is too short");
+                close(socket).await;
+                return;
+            };
+            let metadata = match MetadataRequest::new(req, body, advertised_address) {
+                Ok(request) => request,
+                Err(e) => {
+                    eprintln!("Error while parsing metadata request: {e:?}");
+                    close(socket).await;
+                    return;
+                }
+            };
+            let response = match metadata.get_response() {
+                Ok(val) => val,
+                Err(e) => {
+                    eprintln!("Error while parsing api request: {e:?}");
+                    return;
+                }
+            };
+            respond(socket, &response[..]).await;
+        }
         Request::ApiVersions => {
-            let api_versions = match ApiVersionRequest::new(req, &buf[past_base..]) {
+            let Some(body) = buf.get(past_base..) else {
+                eprintln!("ApiVersions request is too short");
+                close(socket).await;
+                return;
+            };
+            let api_versions = match ApiVersionRequest::new(req, body) {
                 Ok(api_version) => api_version,
                 Err(e) => {
                     eprintln!("Error while parsing api request: {e:?}");
+                    close(socket).await;
                     return;
                 }
             };
@@ -53,10 +100,16 @@ pub async fn dispatch_request(req: RequestBase, buf: &mut BytesMut, socket: &mut
             respond(socket, &response[..]).await;
         }
         Request::DescribeTopicsPartitions => {
-            let describe_t_p = match DescribeTopicPartitions::new(req, &buf[past_base + 1..]) {
+            let Some(body) = buf.get(past_base + 1..) else {
+                eprintln!("DescribeTopicPartitions request is too short");
+                close(socket).await;
+                return;
+            };
+            let describe_t_p = match DescribeTopicPartitions::new(req, body) {
                 Ok(request) => request,
                 Err(e) => {
                     eprintln!("Error while parsing describe topics partitions: {e:?}");
+                    close(socket).await;
                     return;
                 }
             };
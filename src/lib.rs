@@ -3,3 +3,5 @@ pub mod protocol;
 pub mod rpc;
 
 pub mod handler;
+
+pub mod server;
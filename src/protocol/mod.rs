@@ -2,7 +2,7 @@ use anyhow::Error;
 use bytes::BytesMut;
 use types::nullstring::{NullableString, NullableStringError};
 
-use crate::rpc::encode::Encode;
+use crate::rpc::Encode;
 
 pub mod schema;
 pub mod types;
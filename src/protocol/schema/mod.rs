@@ -1,6 +1,6 @@
 use bytes::BytesMut;
 
-use crate::rpc::decode::DecodeError;
+use crate::rpc::DecodeError;
 
 pub mod requests;
 
@@ -10,7 +10,7 @@ use crate::{
         types::compactstring::{CompactString, CompactValueParseError},
         RequestBase,
     },
-    rpc::{decode::DecodeError, encode::Encode},
+    rpc::{DecodeError, Encode},
 };
 
 use super::is_version_supported;
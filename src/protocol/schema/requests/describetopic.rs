@@ -11,7 +11,7 @@ use crate::{
         },
         RequestBase,
     },
-    rpc::encode::Encode,
+    rpc::{DecodeError, Encode},
 };
 
 pub struct DescribeTopicPartitions {
@@ -73,7 +73,13 @@ impl DescribeTopicPartitions {
         buf: &[u8],
     ) -> Result<DescribeTopicPartitions, anyhow::Error> {
         let (topics_array, offset) = CompactArray::<TopicStr>::new(buf)?;
-        let response_partition_limit = i32::from_be_bytes(buf[offset..(offset + 4)].try_into()?);
+        let response_partition_limit = i32::from_be_bytes(
+            buf.get(offset..(offset + 4))
+                .ok_or_else(|| {
+                    DecodeError::InvalidBuffer("Missing response partition limit".to_string())
+                })?
+                .try_into()?,
+        );
         Ok(DescribeTopicPartitions {
             base_request,
             topics_array,
@@ -85,7 +91,7 @@ impl DescribeTopicPartitions {
 }
 
 impl Respond for DescribeTopicPartitions {
-    fn get_response(&self) -> Result<bytes::BytesMut, crate::rpc::decode::DecodeError> {
+    fn get_response(&self) -> Result<bytes::BytesMut, DecodeError> {
         let mut message = BytesMut::new();
         message.put_i32(self.base_request.correlation_id);
         message.put_u8(0x00);
@@ -0,0 +1,356 @@
+use std::{fmt::Debug, net::SocketAddr};
+
+use bytes::{BufMut, BytesMut};
+
+use crate::{
+    protocol::{
+        schema::Respond,
+        types::{
+            compactarray::CompactArray, compactstring::CompactString, decode_varint, encode_zigzag,
+            topicstr::TopicStr, CompactEncode,
+        },
+        RequestBase,
+    },
+    rpc::{DecodeError, Encode},
+};
+
+use super::is_version_supported;
+
+static BROKER_NODE_ID: i32 = 1;
+
+pub struct MetadataRequest {
+    pub base_request: RequestBase,
+    /// `None` when the client sent a null array, which means "all topics".
+    pub topics: Option<CompactArray<TopicStr>>,
+    pub allow_auto_topic_creation: bool,
+    pub include_topic_authorized_operations: bool,
+    /// Address the client reached this broker on, advertised back as the only broker.
+    pub advertised_address: SocketAddr,
+}
+
+pub struct Broker {
+    node_id: i32,
+    host: String,
+    port: i32,
+    tag_buffer: u8,
+}
+
+impl Encode for Broker {
+    fn encode(&self, buf: &mut BytesMut) {
+        buf.put_i32(self.node_id);
+        buf.put(&encode_zigzag(self.host.len() as u64 + 1)[..]);
+        buf.put(self.host.as_bytes());
+        buf.put_i32(self.port);
+        // rack (null)
+        buf.put_u8(0x00);
+        buf.put_u8(self.tag_buffer);
+    }
+}
+
+pub struct TopicMetadata<'a> {
+    error: i16,
+    name: &'a CompactString,
+    is_internal: u8,
+    partitions: CompactArray<CompactString>,
+    authorized_operations: i32,
+    tag_buffer: u8,
+}
+
+impl Encode for TopicMetadata<'_> {
+    fn encode(&self, buf: &mut BytesMut) {
+        buf.put_i16(self.error);
+        self.name.encode_compact(buf);
+        buf.put_u8(self.is_internal);
+        self.partitions.encode(buf);
+        buf.put_i32(self.authorized_operations);
+        buf.put_u8(self.tag_buffer);
+    }
+}
+
+impl TopicMetadata<'_> {
+    fn new(name: &CompactString, include_authorized_operations: bool) -> TopicMetadata<'_> {
+        TopicMetadata {
+            error: 3,
+            name,
+            is_internal: 0,
+            partitions: CompactArray { elements: vec![] },
+            authorized_operations: if include_authorized_operations {
+                0x0000_0df8
+            } else {
+                i32::MIN
+            },
+            tag_buffer: 0,
+        }
+    }
+}
+
+impl Debug for TopicMetadata<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopicMetadata")
+            .field("name", &self.name.value)
+            .finish()
+    }
+}
+
+impl MetadataRequest {
+    /// Creates a new `MetadataRequest` from the provided `RequestBase` and a byte slice.
+    ///
+    /// The buffer is expected to start at the topics array, right after the request header
+    /// tag buffer. A null topics array (varint `0`) is kept as `None` and means that the
+    /// client is asking for every topic known to the broker.
+    ///
+    /// `advertised_address` is the address returned as the broker in the response, usually
+    /// the local address of the connection the request came in on.
+    ///
+    /// Only the v9 layout is handled, since v10 adds a topic id to every topic and earlier
+    /// versions are not flexible, so `supported_versions.json` should only list version 9.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request version is not listed in `supported_versions.json`
+    /// for this key, if the topics array cannot be parsed or if the buffer ends before the
+    /// request flags.
+    pub fn new(
+        base_request: RequestBase,
+        buf: &[u8],
+        advertised_address: SocketAddr,
+    ) -> Result<MetadataRequest, anyhow::Error> {
+        if !is_version_supported(
+            "supported_versions.json",
+            base_request.api_key,
+            base_request.api_version,
+        )? {
+            return Err(DecodeError::InvalidBuffer(format!(
+                "Unsupported metadata version: {}",
+                base_request.api_version
+            ))
+            .into());
+        }
+
+        let (length, size) = decode_varint(buf)?;
+        let (topics, mut offset) = if length == 0 {
+            (None, size)
+        } else {
+            let (topics, offset) = CompactArray::<TopicStr>::new(buf)?;
+            (Some(topics), offset)
+        };
+
+        let allow_auto_topic_creation = *buf
+            .get(offset)
+            .ok_or_else(|| DecodeError::InvalidBuffer("Missing auto topic creation flag".into()))?
+            != 0;
+        // skip include_cluster_authorized_operations
+        offset += 2;
+
+        let include_topic_authorized_operations = *buf.get(offset).ok_or_else(|| {
+            DecodeError::InvalidBuffer("Missing topic authorized operations flag".into())
+        })? != 0;
+
+        Ok(MetadataRequest {
+            base_request,
+            topics,
+            allow_auto_topic_creation,
+            include_topic_authorized_operations,
+            advertised_address,
+        })
+    }
+}
+
+impl Respond for MetadataRequest {
+    fn get_response(&self) -> Result<BytesMut, DecodeError> {
+        let brokers = [Broker {
+            node_id: BROKER_NODE_ID,
+            host: self.advertised_address.ip().to_string(),
+            port: i32::from(self.advertised_address.port()),
+            tag_buffer: 0,
+        }];
+        // No topics can be resolved from the cluster metadata yet, so a null array yields
+        // an empty list and every requested topic is reported as unknown.
+        let topics: Vec<TopicMetadata> = match &self.topics {
+            Some(topics) => topics
+                .elements
+                .iter()
+                .map(|topic| {
+                    TopicMetadata::new(&topic.value, self.include_topic_authorized_operations)
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let mut message = BytesMut::new();
+        message.put_i32(self.base_request.correlation_id);
+        message.put_u8(0x00);
+        // throttle ms
+        message.put(&[0x00, 0x00, 0x00, 0x00][..]);
+        message.put(&encode_zigzag(brokers.len() as u64 + 1)[..]);
+        brokers
+            .iter()
+            .for_each(|broker| broker.encode(&mut message));
+        // cluster id (null)
+        message.put_u8(0x00);
+        message.put_i32(BROKER_NODE_ID);
+        message.put(&encode_zigzag(topics.len() as u64 + 1)[..]);
+        topics.iter().for_each(|topic| topic.encode(&mut message));
+        // cluster authorized operations
+        message.put_i32(i32::MIN);
+        message.put_u8(0x00);
+
+        let mut response = BytesMut::with_capacity(message.len() + 4);
+        let len = message.len() as i32;
+        response.put(&len.to_be_bytes()[..]);
+        response.put(&message[..]);
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Buf;
+
+    use super::*;
+
+    fn base_request(api_version: i16) -> RequestBase {
+        let mut buf = BytesMut::new();
+        buf.put_i32(10); // size
+        buf.put_i16(3); // api_key
+        buf.put_i16(api_version);
+        buf.put_i32(7); // correlation_id
+        buf.put_i16(-1); // client_id_size
+        RequestBase::new(&buf).unwrap()
+    }
+
+    fn advertised_address() -> SocketAddr {
+        "10.0.0.5:19092".parse().unwrap()
+    }
+
+    fn request_with_topic(include_topic_authorized_operations: u8) -> MetadataRequest {
+        let buf: &[u8] = &[
+            0x02, // one topic
+            0x04,
+            b'F',
+            b'o',
+            b'o',
+            0x00, // "Foo" + tag buffer
+            0x00, // allow_auto_topic_creation
+            0x00, // include_cluster_authorized_operations
+            include_topic_authorized_operations,
+            0x00, // tag buffer
+        ];
+        MetadataRequest::new(base_request(9), buf, advertised_address()).unwrap()
+    }
+
+    /// Skips the response header and the broker array, leaving `res` at the topics array.
+    fn skip_to_topics(res: &mut &[u8]) {
+        res.advance(4 + 4 + 1 + 4); // size, correlation id, tag buffer, throttle ms
+        res.advance(1 + 4); // brokers length, node id
+        let host_len = res.get_u8() as usize - 1;
+        res.advance(host_len + 4 + 1 + 1); // host, port, rack, tag buffer
+        res.advance(1 + 4); // cluster id, controller id
+    }
+
+    #[test]
+    fn test_null_topics_means_all_topics() {
+        let buf: &[u8] = &[
+            0x00, // null topics array
+            0x01, // allow_auto_topic_creation
+            0x00, // include_cluster_authorized_operations
+            0x01, // include_topic_authorized_operations
+            0x00, // tag buffer
+        ];
+
+        let request = MetadataRequest::new(base_request(9), buf, advertised_address()).unwrap();
+
+        assert!(request.topics.is_none());
+        assert!(request.allow_auto_topic_creation);
+        assert!(request.include_topic_authorized_operations);
+    }
+
+    #[test]
+    fn test_topics_and_flags() {
+        let request = request_with_topic(0x01);
+
+        let topics = request.topics.unwrap();
+        assert_eq!(topics.elements.len(), 1);
+        assert_eq!(topics.elements[0].value.value, "Foo");
+        assert!(!request.allow_auto_topic_creation);
+        assert!(request.include_topic_authorized_operations);
+    }
+
+    #[test]
+    fn test_missing_flags() {
+        let buf: &[u8] = &[0x00];
+
+        let result = MetadataRequest::new(base_request(9), buf, advertised_address());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        let buf: &[u8] = &[0x00, 0x01, 0x00, 0x00, 0x00];
+
+        for version in [0, 8, 10, 12] {
+            let result = MetadataRequest::new(base_request(version), buf, advertised_address());
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_response_advertises_broker() {
+        let buf: &[u8] = &[0x00, 0x01, 0x00, 0x00, 0x00];
+        let request = MetadataRequest::new(base_request(9), buf, advertised_address()).unwrap();
+
+        let response = request.get_response().unwrap();
+        let mut res = &response[..];
+
+        assert_eq!(res.get_i32() as usize, response.len() - 4);
+        assert_eq!(res.get_i32(), 7);
+        res.advance(1 + 4); // tag buffer, throttle ms
+        assert_eq!(res.get_u8(), 0x02);
+        assert_eq!(res.get_i32(), BROKER_NODE_ID);
+        assert_eq!(res.get_u8(), 8 + 1);
+        assert_eq!(&res[..8], b"10.0.0.5");
+        res.advance(8);
+        assert_eq!(res.get_i32(), 19092);
+    }
+
+    #[test]
+    fn test_response_null_topics_is_empty() {
+        let buf: &[u8] = &[0x00, 0x01, 0x00, 0x00, 0x00];
+        let request = MetadataRequest::new(base_request(9), buf, advertised_address()).unwrap();
+
+        let response = request.get_response().unwrap();
+        let mut res = &response[..];
+        skip_to_topics(&mut res);
+
+        assert_eq!(res.get_u8(), 0x01);
+        assert_eq!(res.get_i32(), i32::MIN);
+        assert_eq!(res.get_u8(), 0x00);
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_response_topics() {
+        for (flag, authorized_operations) in [(0x01, 0x0000_0df8), (0x00, i32::MIN)] {
+            let response = request_with_topic(flag).get_response().unwrap();
+            let mut res = &response[..];
+            skip_to_topics(&mut res);
+
+            assert_eq!(res.get_u8(), 0x02);
+            // unknown topic or partition
+            assert_eq!(res.get_i16(), 3);
+            assert_eq!(res.get_u8(), 0x04);
+            assert_eq!(&res[..3], b"Foo");
+            res.advance(3);
+            assert_eq!(res.get_u8(), 0x00); // is_internal
+            assert_eq!(res.get_u8(), 0x01); // partitions
+            assert_eq!(res.get_i32(), authorized_operations);
+            assert_eq!(res.get_u8(), 0x00); // tag buffer
+
+            // cluster authorized operations
+            assert_eq!(res.get_i32(), i32::MIN);
+            assert_eq!(res.get_u8(), 0x00);
+            assert!(res.is_empty());
+        }
+    }
+}
@@ -7,6 +7,8 @@ pub mod apiversions;
 
 pub mod describetopic;
 
+pub mod metadata;
+
 /// Checks if a given version is supported for a specific key.
 ///
 /// This function reads a JSON file (`supported_versions.json`) which contains a list
@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use bytes::BufMut;
 
-use crate::rpc::{decode::Decode, encode::Encode};
+use crate::rpc::{Decode, Encode};
 
 use super::{compactstring::CompactValueParseError, decode_varint, Offset};
 
@@ -22,10 +22,7 @@ use std::{
 use bytes::BufMut;
 use thiserror::Error;
 
-use crate::rpc::{
-    decode::{Decode, DecodeError},
-    encode::Encode,
-};
+use crate::rpc::{Decode, DecodeError, Encode};
 
 use super::{decode_varint, encode_zigzag, CompactEncode, Offset};
 
@@ -127,7 +124,7 @@ impl CompactString {
 }
 
 impl Decode<CompactString> for CompactString {
-    fn decode(buf: &[u8]) -> Result<CompactString, crate::rpc::decode::DecodeError> {
+    fn decode(buf: &[u8]) -> Result<CompactString, DecodeError> {
         // Assuming CompactString has a constructor `new` that takes a buffer and parses it
         match CompactString::new(buf) {
             Ok(val) => Ok(val),
@@ -155,7 +152,7 @@ impl CompactEncode for CompactString {
 }
 
 impl Decode<CompactString> for [u8] {
-    fn decode(buf: &[u8]) -> Result<CompactString, crate::rpc::decode::DecodeError> {
+    fn decode(buf: &[u8]) -> Result<CompactString, DecodeError> {
         match CompactString::new(buf) {
             Ok(val) => Ok(val),
             Err(e) => Err(DecodeError::InvalidBuffer(format!(
@@ -6,6 +6,7 @@ pub mod compactstring;
 pub mod nullstring;
 pub mod partition;
 pub mod record;
+pub mod recordbatch;
 pub mod topicstr;
 
 pub trait Offset {
@@ -0,0 +1,210 @@
+use std::fmt::Debug;
+
+use bytes::Buf;
+
+use crate::rpc::{Decode, DecodeError};
+
+use super::Offset;
+
+/// Size of the fields preceding `batch_length`'s payload (`base_offset` + `batch_length`).
+const LOG_OVERHEAD: usize = 12;
+/// Size of the fixed record batch header, up to and including `records_count`.
+const HEADER_SIZE: usize = 61;
+/// Only the v2 batch format uses CRC32C over the bytes from `attributes` onwards.
+const MAGIC: i8 = 2;
+
+pub struct RecordBatch {
+    pub base_offset: i64,
+    pub batch_length: i32,
+    pub partition_leader_epoch: i32,
+    pub magic: i8,
+    pub crc: u32,
+    pub attributes: i16,
+    pub last_offset_delta: i32,
+    pub base_timestamp: i64,
+    pub max_timestamp: i64,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub base_sequence: i32,
+    pub records_count: i32,
+    pub records: Vec<u8>,
+}
+
+impl Debug for RecordBatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordBatch")
+            .field("base_offset", &self.base_offset)
+            .field("batch_length", &self.batch_length)
+            .field("crc", &self.crc)
+            .field("records_count", &self.records_count)
+            .finish()
+    }
+}
+
+impl Offset for RecordBatch {
+    fn get_offset(&self) -> u64 {
+        LOG_OVERHEAD as u64 + self.batch_length as u64
+    }
+}
+
+impl Decode<RecordBatch> for RecordBatch {
+    /// Decodes a record batch, checking its CRC32C (Castagnoli) against the bytes going from
+    /// `attributes` to the end of the batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecodeError::InvalidBuffer` if the buffer is shorter than the batch or holds
+    /// a pre-v2 message set, and `DecodeError::CrcMismatch` if the stored CRC doesn't match
+    /// the batch contents.
+    fn decode(buf: &[u8]) -> Result<RecordBatch, DecodeError> {
+        if buf.len() < HEADER_SIZE {
+            return Err(DecodeError::InvalidBuffer(
+                "Buffer is too small to hold a record batch header".to_string(),
+            ));
+        }
+
+        let batch_length = (&buf[8..LOG_OVERHEAD]).get_i32();
+        let end = usize::try_from(batch_length)
+            .ok()
+            .map(|len| LOG_OVERHEAD + len)
+            .filter(|end| *end >= HEADER_SIZE && *end <= buf.len())
+            .ok_or_else(|| {
+                DecodeError::InvalidBuffer(format!(
+                    "Record batch length {batch_length} does not fit the buffer"
+                ))
+            })?;
+
+        let mut batch = &buf[..end];
+        let base_offset = batch.get_i64();
+        batch.advance(4); // batch_length
+        let partition_leader_epoch = batch.get_i32();
+
+        let magic = batch.get_i8();
+        if magic != MAGIC {
+            return Err(DecodeError::InvalidBuffer(format!(
+                "Unsupported record batch magic {magic}"
+            )));
+        }
+
+        let crc = batch.get_u32();
+        let actual = crc32c::crc32c(batch);
+        if crc != actual {
+            return Err(DecodeError::CrcMismatch {
+                expected: crc,
+                actual,
+            });
+        }
+
+        Ok(RecordBatch {
+            base_offset,
+            batch_length,
+            partition_leader_epoch,
+            magic,
+            crc,
+            attributes: batch.get_i16(),
+            last_offset_delta: batch.get_i32(),
+            base_timestamp: batch.get_i64(),
+            max_timestamp: batch.get_i64(),
+            producer_id: batch.get_i64(),
+            producer_epoch: batch.get_i16(),
+            base_sequence: batch.get_i32(),
+            records_count: batch.get_i32(),
+            records: batch.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+
+    use super::*;
+
+    fn generate_batch() -> BytesMut {
+        let records: &[u8] = &[0x0e, 0x00, 0x00, 0x00, 0x01, 0x02, b'h', b'i', 0x00];
+
+        let mut body = BytesMut::new();
+        body.put_i16(0); // attributes
+        body.put_i32(0); // last_offset_delta
+        body.put_i64(1_726_045_943_832); // base_timestamp
+        body.put_i64(1_726_045_943_832); // max_timestamp
+        body.put_i64(-1); // producer_id
+        body.put_i16(-1); // producer_epoch
+        body.put_i32(-1); // base_sequence
+        body.put_i32(1); // records_count
+        body.put(records);
+
+        let mut buf = BytesMut::new();
+        buf.put_i64(0); // base_offset
+        buf.put_i32((4 + 1 + 4 + body.len()) as i32); // batch_length
+        buf.put_i32(1); // partition_leader_epoch
+        buf.put_i8(2); // magic
+        buf.put_u32(crc32c::crc32c(&body[..]));
+        buf.put(&body[..]);
+        buf
+    }
+
+    #[test]
+    fn test_decode_known_crc() {
+        // Feature level record from a __cluster_metadata log written by Kafka.
+        let buf: &[u8] = &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x4f, //
+            0x00, 0x00, 0x00, 0x01, 0x02, 0xb0, 0x69, 0x45, 0x7c, 0x00, 0x00, 0x00, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x91, 0xe0, 0x5a, 0xf8, 0x18, 0x00, //
+            0x00, 0x01, 0x91, 0xe0, 0x5a, 0xf8, 0x18, 0xff, 0xff, 0xff, 0xff, 0xff, //
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, //
+            0x01, 0x3a, 0x00, 0x00, 0x00, 0x01, 0x2e, 0x01, 0x0c, 0x00, 0x11, 0x6d, //
+            0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61, 0x2e, 0x76, 0x65, 0x72, 0x73, //
+            0x69, 0x6f, 0x6e, 0x00, 0x14, 0x00, 0x00,
+        ];
+
+        let batch = RecordBatch::decode(buf).unwrap();
+
+        assert_eq!(batch.crc, 0xb069_457c);
+        assert_eq!(batch.base_offset, 1);
+        assert_eq!(batch.records_count, 1);
+        assert_eq!(batch.get_offset(), buf.len() as u64);
+    }
+
+    #[test]
+    fn test_decode_valid_crc() {
+        let buf = generate_batch();
+
+        let batch = RecordBatch::decode(&buf[..]).unwrap();
+
+        assert_eq!(batch.magic, 2);
+        assert_eq!(batch.records_count, 1);
+        assert_eq!(batch.get_offset(), buf.len() as u64);
+        assert_eq!(batch.records.len(), 9);
+    }
+
+    #[test]
+    fn test_decode_flipped_byte() {
+        let mut buf = generate_batch();
+        let last = buf.len() - 2;
+        buf[last] ^= 0xff;
+
+        let result = RecordBatch::decode(&buf[..]);
+
+        assert!(matches!(result, Err(DecodeError::CrcMismatch { .. })));
+    }
+
+    #[test]
+    fn test_decode_unsupported_magic() {
+        let mut buf = generate_batch();
+        buf[16] = 1;
+
+        let result = RecordBatch::decode(&buf[..]);
+
+        assert!(matches!(result, Err(DecodeError::InvalidBuffer(_))));
+    }
+
+    #[test]
+    fn test_decode_truncated_batch() {
+        let buf = generate_batch();
+
+        let result = RecordBatch::decode(&buf[..buf.len() - 1]);
+
+        assert!(matches!(result, Err(DecodeError::InvalidBuffer(_))));
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use crate::rpc::decode::{Decode, DecodeError};
+use crate::rpc::{Decode, DecodeError};
 
 use super::{compactstring::CompactString, Offset};
 
@@ -25,7 +25,7 @@ impl Offset for TopicStr {
 }
 
 impl Decode<TopicStr> for TopicStr {
-    fn decode(buf: &[u8]) -> Result<TopicStr, crate::rpc::decode::DecodeError> {
+    fn decode(buf: &[u8]) -> Result<TopicStr, DecodeError> {
         let value = TopicStr::new(buf).map_err(|e| DecodeError::InvalidBuffer(format!("{e:?}")))?;
         Ok(value)
     }
@@ -5,6 +5,7 @@ use thiserror::Error;
 #[derive(Error)]
 pub enum DecodeError {
     InvalidBuffer(String),
+    CrcMismatch { expected: u32, actual: u32 },
 }
 
 impl fmt::Display for DecodeError {
@@ -13,6 +14,12 @@ impl fmt::Display for DecodeError {
             Self::InvalidBuffer(t) => {
                 write!(f, "Error while decoding buffer: {t}")
             }
+            Self::CrcMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "CRC mismatch: expected {expected:#010x}, got {actual:#010x}"
+                )
+            }
         }
     }
 }
@@ -23,6 +30,12 @@ impl fmt::Debug for DecodeError {
             Self::InvalidBuffer(t) => {
                 write!(f, "Error while decoding buffer: {t}")
             }
+            Self::CrcMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "CRC mismatch: expected {expected:#010x}, got {actual:#010x}"
+                )
+            }
         }
     }
 }
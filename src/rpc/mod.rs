@@ -1,3 +1,6 @@
 pub mod decode;
 
 pub mod encode;
+
+pub use decode::{Decode, DecodeError};
+pub use encode::Encode;
@@ -0,0 +1,323 @@
+use std::{io::ErrorKind, sync::Arc, time::Duration};
+
+use bytes::{BufMut, BytesMut};
+use tokio::{
+    io::AsyncReadExt,
+    net::{TcpListener, TcpStream},
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::{sleep, timeout},
+};
+
+use crate::{handler::dispatch_request, protocol::RequestBase};
+
+/// Pause after a failed `accept`, giving the process a chance to free descriptors.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+pub struct ServerConfig {
+    pub address: String,
+    pub max_connections: usize,
+    pub idle_timeout: Duration,
+    /// Largest request frame accepted, so a bogus size can't make us allocate unbounded memory.
+    pub max_frame_size: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            address: "127.0.0.1:9092".to_string(),
+            max_connections: 1024,
+            idle_timeout: Duration::from_secs(30),
+            // same as Kafka's socket.request.max.bytes
+            max_frame_size: 100 * 1024 * 1024,
+        }
+    }
+}
+
+/// Binds to `config.address` and serves connections forever.
+///
+/// # Errors
+///
+/// Returns an error if the address cannot be bound.
+pub async fn run_server(config: ServerConfig) -> Result<(), std::io::Error> {
+    let listener = TcpListener::bind(&config.address).await?;
+    println!("Starting server at {}", config.address);
+
+    serve(listener, config).await;
+    Ok(())
+}
+
+/// Accepts connections from `listener`, spawning a task per connection.
+///
+/// At most `config.max_connections` connections are served at once; any connection accepted
+/// past that limit is closed right away so the accept loop never blocks. Connections that do
+/// not send a full request frame within `config.idle_timeout`, or that send a frame larger
+/// than `config.max_frame_size`, are closed.
+///
+/// Accept errors, such as running out of file descriptors, are logged and retried after a
+/// short pause rather than stopping the server.
+pub async fn serve(listener: TcpListener, config: ServerConfig) {
+    let connections = Arc::new(Semaphore::new(config.max_connections));
+
+    loop {
+        let (socket, addr) = match listener.accept().await {
+            Ok(val) => val,
+            Err(e) => {
+                eprintln!("failed to accept connection; err = {e:?}");
+                sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+
+        let Ok(permit) = Arc::clone(&connections).try_acquire_owned() else {
+            eprintln!("Connection limit reached, closing connection from {addr}");
+            drop(socket);
+            continue;
+        };
+
+        tokio::spawn(handle_connection(
+            socket,
+            permit,
+            config.idle_timeout,
+            config.max_frame_size,
+        ));
+    }
+}
+
+/// Reads one request frame, size prefix included, returning `None` if the client closed the
+/// connection before sending anything.
+async fn read_frame(
+    socket: &mut TcpStream,
+    max_frame_size: usize,
+) -> Result<Option<BytesMut>, std::io::Error> {
+    let size = match socket.read_i32().await {
+        Ok(size) => size,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let size = usize::try_from(size)
+        .ok()
+        .filter(|size| *size <= max_frame_size)
+        .ok_or_else(|| {
+            std::io::Error::new(ErrorKind::InvalidData, format!("invalid frame size {size}"))
+        })?;
+
+    let mut buf = BytesMut::with_capacity(size + 4);
+    buf.put_i32(size as i32);
+    buf.resize(size + 4, 0);
+    socket.read_exact(&mut buf[4..]).await?;
+
+    Ok(Some(buf))
+}
+
+async fn handle_connection(
+    mut socket: TcpStream,
+    _permit: OwnedSemaphorePermit,
+    idle: Duration,
+    max_frame_size: usize,
+) {
+    loop {
+        let mut buf = match timeout(idle, read_frame(&mut socket, max_frame_size)).await {
+            Ok(Ok(Some(buf))) => buf,
+            Ok(Ok(None)) => {
+                println!("Connection closed by client.");
+                return;
+            }
+            Ok(Err(e)) => {
+                eprintln!("failed to read from socket; err = {e:?}");
+                return;
+            }
+            Err(_) => {
+                println!("Closing idle connection.");
+                return;
+            }
+        };
+
+        let base_request = if let Ok(val) = RequestBase::new(&buf) {
+            val
+        } else {
+            eprintln!("Failed to parse request");
+            return;
+        };
+
+        dispatch_request(base_request, &mut buf, &mut socket).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    async fn start(config: ServerConfig) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, config));
+        addr
+    }
+
+    async fn is_closed_within(socket: &mut TcpStream, wait: Duration) -> bool {
+        let mut buf = [0u8; 1];
+        matches!(
+            timeout(wait, socket.read(&mut buf)).await,
+            Ok(Ok(0) | Err(_))
+        )
+    }
+
+    async fn is_closed(socket: &mut TcpStream) -> bool {
+        is_closed_within(socket, Duration::from_secs(2)).await
+    }
+
+    async fn send_frame(socket: &mut TcpStream, body: &[u8]) -> Result<(), std::io::Error> {
+        let mut request = BytesMut::new();
+        request.put_i32(body.len() as i32);
+        request.put(body);
+        socket.write_all(&request).await
+    }
+
+    /// Sends an `ApiVersions` request and returns the correlation id of the response.
+    async fn api_versions(socket: &mut TcpStream) -> Result<i32, std::io::Error> {
+        let mut body = BytesMut::new();
+        body.put_i16(18); // api_key
+        body.put_i16(4); // api_version
+        body.put_i32(7); // correlation_id
+        body.put_i16(-1); // client_id
+        body.put_u8(0); // tag buffer
+        body.put(&[0x02, b'a', 0x02, b'1', 0x00][..]); // client software name/version
+        send_frame(socket, &body).await?;
+
+        let size = socket.read_i32().await?;
+        let correlation_id = socket.read_i32().await?;
+        let mut rest = vec![0; size as usize - 4];
+        socket.read_exact(&mut rest).await?;
+        Ok(correlation_id)
+    }
+
+    #[tokio::test]
+    async fn test_idle_connection_is_closed() {
+        let addr = start(ServerConfig {
+            idle_timeout: Duration::from_millis(50),
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        assert!(is_closed(&mut socket).await);
+    }
+
+    #[tokio::test]
+    async fn test_connection_over_limit_is_closed() {
+        let addr = start(ServerConfig {
+            max_connections: 1,
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let _first = TcpStream::connect(addr).await.unwrap();
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        assert!(is_closed(&mut second).await);
+    }
+
+    #[tokio::test]
+    async fn test_trickled_frame_is_closed() {
+        let idle = Duration::from_millis(100);
+        let addr = start(ServerConfig {
+            idle_timeout: idle,
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        let _ = socket.write_i32(64).await;
+        // Each byte arrives well within the idle timeout, but the frame never completes.
+        for _ in 0..20 {
+            let _ = socket.write_u8(0).await;
+            sleep(idle / 4).await;
+        }
+
+        assert!(is_closed_within(&mut socket, Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_permit_released_on_close() {
+        let addr = start(ServerConfig {
+            max_connections: 1,
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        assert_eq!(api_versions(&mut first).await.unwrap(), 7);
+        drop(first);
+
+        // The permit is only released once the server notices the close, so retry briefly.
+        for _ in 0..20 {
+            let mut socket = TcpStream::connect(addr).await.unwrap();
+            if let Ok(correlation_id) = api_versions(&mut socket).await {
+                assert_eq!(correlation_id, 7);
+                return;
+            }
+            sleep(Duration::from_millis(25)).await;
+        }
+        panic!("no connection was served after the first one closed");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_metadata_version_is_closed() {
+        let addr = start(ServerConfig::default()).await;
+
+        let mut body = BytesMut::new();
+        body.put_i16(3); // api_key
+        body.put_i16(8); // api_version
+        body.put_i32(7); // correlation_id
+        body.put_i16(-1); // client_id
+        body.put_u8(0); // tag buffer
+        body.put(&[0x00, 0x01, 0x00, 0x00, 0x00][..]); // null topics + flags
+
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        send_frame(&mut socket, &body).await.unwrap();
+
+        assert!(is_closed(&mut socket).await);
+    }
+
+    #[tokio::test]
+    async fn test_header_only_frame_is_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        for (api_key, api_version) in [(3, 9), (18, 4), (75, 0)] {
+            let mut body = BytesMut::new();
+            body.put_i16(api_key);
+            body.put_i16(api_version);
+            body.put_i32(7); // correlation_id
+            body.put_i16(-1); // client_id
+
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            send_frame(&mut client, &body).await.unwrap();
+
+            // Run the connection on this task so a panic while handling it fails the test.
+            let permit = Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap();
+            let (_, closed) = tokio::join!(
+                handle_connection(socket, permit, Duration::from_millis(200), 1024),
+                is_closed(&mut client)
+            );
+            assert!(closed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_is_closed() {
+        let addr = start(ServerConfig {
+            max_frame_size: 16,
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        socket.write_i32(17).await.unwrap();
+
+        assert!(is_closed(&mut socket).await);
+    }
+}